@@ -0,0 +1,93 @@
+//! Generates typed bindings for each zkEVM contract and embeds its deploy bytecode as a `const`
+//! next to the generated binding, so that `examples/deploy.rs` no longer has to locate the
+//! Hardhat artifact on disk at runtime.
+
+use ethers_contract::Abigen;
+use ethers_solc::HardhatArtifact;
+use std::{env, fs, path::Path};
+
+/// Contracts to generate bindings for, as `(subdirectory under artifacts/contracts, contract
+/// name, generated module name)`. Mirrors the `mk_deploy!` invocations in `examples/deploy.rs`.
+const CONTRACTS: &[(&str, &str, &str)] = &[
+    ("", "PolygonZkEVM", "polygon_zk_evm"),
+    ("", "PolygonZkEVMBridge", "polygon_zk_evm_bridge"),
+    (
+        "",
+        "PolygonZkEVMGlobalExitRootL2",
+        "polygon_zk_evm_global_exit_root_l2",
+    ),
+    (
+        "",
+        "PolygonZkEVMGlobalExitRoot",
+        "polygon_zk_evm_global_exit_root",
+    ),
+    ("", "PolygonZkEVMTimelock", "polygon_zk_evm_timelock"),
+    ("verifiers", "Verifier", "verifier"),
+    (
+        "mocks",
+        "VerifierRollupHelperMock",
+        "verifier_rollup_helper_mock",
+    ),
+    ("mocks", "ERC20PermitMock", "erc20_permit_mock"),
+];
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let artifacts_dir =
+        Path::new(&manifest_dir).join("../zkevm-contracts/artifacts/contracts");
+    let out_dir = env::var("OUT_DIR").unwrap();
+
+    for (prefix, contract, module) in CONTRACTS {
+        let path = artifacts_dir
+            .join(prefix)
+            .join(format!("{contract}.sol"))
+            .join(format!("{contract}.json"));
+        println!("cargo:rerun-if-changed={}", path.display());
+
+        let artifact: HardhatArtifact = serde_json::from_reader(
+            fs::File::open(&path).unwrap_or_else(|_| panic!("unable to open artifact {path:?}")),
+        )
+        .unwrap_or_else(|err| panic!("invalid Hardhat artifact {path:?}: {err}"));
+
+        let bindings_file = Path::new(&out_dir).join(format!("{module}.rs"));
+        Abigen::new(contract, serde_json::to_string(&artifact.abi).unwrap())
+            .unwrap_or_else(|err| panic!("failed to load ABI for {contract}: {err}"))
+            .generate()
+            .unwrap_or_else(|err| panic!("failed to generate bindings for {contract}: {err}"))
+            .write_to_file(&bindings_file)
+            .unwrap_or_else(|err| panic!("failed to write bindings for {contract}: {err}"));
+
+        // `into_bytes()` yields `ethers`' `Bytes`, whose `Debug` impl prints `Bytes(0x1234..)`,
+        // not a slice literal; convert to a plain `Vec<u8>` so `{:?}` formats as `[18, 52, ..]`.
+        let bytecode: Vec<u8> = artifact
+            .bytecode
+            .unwrap_or_default()
+            .into_bytes()
+            .unwrap_or_default()
+            .to_vec();
+        let deployed_bytecode: Vec<u8> = artifact
+            .deployed_bytecode
+            .unwrap_or_default()
+            .into_bytes()
+            .unwrap_or_default()
+            .to_vec();
+
+        let abi_json = serde_json::to_string(&artifact.abi).unwrap();
+
+        let mut contents = fs::read_to_string(&bindings_file).unwrap();
+        contents.push_str(&format!(
+            "\n/// Deploy bytecode, embedded at compile time from the Hardhat artifact.\n\
+             pub const BYTECODE: &[u8] = &{bytecode:?};\n\
+             /// Deployed (runtime) bytecode, embedded at compile time from the Hardhat artifact.\n\
+             pub const DEPLOYED_BYTECODE: &[u8] = &{deployed_bytecode:?};\n\
+             /// ABI JSON, embedded at compile time from the Hardhat artifact.\n\
+             const ABI_JSON: &str = {abi_json:?};\n\
+             /// Parses the embedded ABI JSON. Used by the `Deploy` impls in `examples/deploy.rs`, which\n\
+             /// need an owned [`Abi`](ethers::core::abi::Abi) to build a [`ContractFactory`](ethers::contract::ContractFactory).\n\
+             pub fn abi() -> ethers::core::abi::Abi {{\n    \
+                 ethers::core::abi::Abi::load(ABI_JSON.as_bytes()).expect(\"embedded ABI is valid JSON\")\n\
+             }}\n"
+        ));
+        fs::write(&bindings_file, contents).unwrap();
+    }
+}
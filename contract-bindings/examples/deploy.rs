@@ -5,7 +5,7 @@ use contract_bindings::bindings::{
     polygon_zk_evm_global_exit_root::PolygonZkEVMGlobalExitRoot,
     polygon_zk_evm_global_exit_root_l2::PolygonZkEVMGlobalExitRootL2,
     polygon_zk_evm_timelock::PolygonZkEVMTimelock,
-    shared_types::{BatchData, InitializePackedParameters},
+    shared_types::InitializePackedParameters,
     verifier::Verifier,
     verifier_rollup_helper_mock::VerifierRollupHelperMock,
 };
@@ -15,12 +15,10 @@ use ethers::{
     prelude::{ContractFactory, SignerMiddleware},
     providers::{Middleware, Provider},
     signers::{coins_bip39::English, MnemonicBuilder, Signer},
-    types::BlockNumber,
     utils::parse_ether,
 };
-use ethers_solc::HardhatArtifact;
 use hex::FromHex;
-use std::{fs, ops::Mul, path::Path, sync::Arc, time::Duration};
+use std::{sync::Arc, time::Duration};
 
 #[async_trait::async_trait]
 pub trait Deploy<M: Middleware> {
@@ -29,55 +27,46 @@ pub trait Deploy<M: Middleware> {
 
 /// Creates a deploy function for the contract.
 ///
-/// If the contract is in a subdirectory of the "artifacts/contracts" directory,
-/// the subdirectory relative to the "artifacts/contracts" directory must be
-/// passed as first argument.
+/// The ABI and bytecode are embedded at compile time by `build.rs`, so deployment no longer
+/// depends on locating the Hardhat artifact on disk, and works from any working directory or a
+/// published crate.
 macro_rules! mk_deploy {
-    ($prefix: tt, $contract:ident) => {
+    ($module:ident, $contract:ident) => {
         #[async_trait::async_trait]
         impl<M: Middleware> Deploy<M> for $contract<M> {
             async fn deploy<T: Tokenize + Send>(client: &Arc<M>, args: T) -> Self {
-                // Ideally we would make our bindings generator script inline
-                // the contract bytecode somewhere in this crate, then the
-                // heuristic for finding the hardhat artifact below would no
-                // longer be necessary.
-                let path = Path::new(env!("CARGO_MANIFEST_DIR"))
-                    .parent()
-                    .unwrap()
-                    .join(format!(
-                        "zkevm-contracts/artifacts/contracts/{}/{}.sol/{}.json",
-                        $prefix,
-                        stringify!($contract),
-                        stringify!($contract)
-                    ));
-                let file = fs::File::open(&path)
-                    .unwrap_or_else(|_| panic!("Unable to open path {:?}", path));
-                let artifact = serde_json::from_reader::<_, HardhatArtifact>(file).unwrap();
-                deploy_artifact(artifact, client, args).await.into()
+                deploy_artifact(
+                    contract_bindings::bindings::$module::abi(),
+                    contract_bindings::bindings::$module::BYTECODE,
+                    client,
+                    args,
+                )
+                .await
+                .into()
             }
         }
     };
 }
 
-mk_deploy!("", PolygonZkEVM);
-mk_deploy!("", PolygonZkEVMBridge);
-mk_deploy!("", PolygonZkEVMGlobalExitRootL2);
-mk_deploy!("", PolygonZkEVMGlobalExitRoot);
-mk_deploy!("", PolygonZkEVMTimelock);
-mk_deploy!("verifiers", Verifier);
-mk_deploy!("mocks", VerifierRollupHelperMock);
-mk_deploy!("mocks", ERC20PermitMock);
+mk_deploy!(polygon_zk_evm, PolygonZkEVM);
+mk_deploy!(polygon_zk_evm_bridge, PolygonZkEVMBridge);
+mk_deploy!(
+    polygon_zk_evm_global_exit_root_l2,
+    PolygonZkEVMGlobalExitRootL2
+);
+mk_deploy!(polygon_zk_evm_global_exit_root, PolygonZkEVMGlobalExitRoot);
+mk_deploy!(polygon_zk_evm_timelock, PolygonZkEVMTimelock);
+mk_deploy!(verifier, Verifier);
+mk_deploy!(verifier_rollup_helper_mock, VerifierRollupHelperMock);
+mk_deploy!(erc20_permit_mock, ERC20PermitMock);
 
 async fn deploy_artifact<M: Middleware, T: Tokenize>(
-    artifact: HardhatArtifact,
+    abi: ethers::abi::Abi,
+    bytecode: &[u8],
     client: &Arc<M>,
     args: T,
 ) -> Contract<M> {
-    let factory = ContractFactory::new(
-        artifact.abi.into(),
-        artifact.bytecode.unwrap().into_bytes().unwrap(),
-        client.clone(),
-    );
+    let factory = ContractFactory::new(abi, bytecode.to_vec().into(), client.clone());
     factory.deploy(args).unwrap().send().await.unwrap()
 }
 
@@ -206,39 +195,9 @@ async fn main() {
         .await
         .unwrap();
 
-    // Try to sequence a batch
-    let l2_tx_data = hex::decode("1234").unwrap();
-    let matic_amount = rollup.get_current_batch_fee().await.unwrap().mul(2u64);
-    let current_timestamp = provider
-        .get_block(BlockNumber::Latest)
-        .await
-        .unwrap()
-        .unwrap()
-        .timestamp;
-    let batch = BatchData {
-        transactions: l2_tx_data.into(),
-        global_exit_root: [0u8; 32],
-        timestamp: current_timestamp.as_u64(),
-        min_forced_timestamp: 0u64,
-    };
-
-    // Approve Matic
-    let matic_trusted: ERC20PermitMock<_> = matic.connect(trusted_sequencer_client.clone()).into();
-    matic_trusted
-        .approve(rollup.address(), matic_amount)
-        .send()
-        .await
-        .unwrap()
-        .await
-        .unwrap();
-
-    let rollup_trusted: PolygonZkEVM<_> = rollup.connect(trusted_sequencer_client).into();
-    let receipt = rollup_trusted
-        .sequence_batches(vec![batch])
-        .send()
-        .await
-        .unwrap()
-        .await
-        .unwrap();
-    assert_eq!(receipt.unwrap().status, Some(1u64.into()));
+    // This example only deploys the contracts and funds the trusted sequencer; ongoing batch
+    // submission is handled by `sequencer::l1_batch::BatchDriver`, which runs for the lifetime of
+    // the sequencer rather than sequencing a single hard-coded batch here.
+    let _ = rollup;
+    let _ = provider;
 }
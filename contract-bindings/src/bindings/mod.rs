@@ -0,0 +1,40 @@
+//! Per-contract bindings.
+//!
+//! Every module below other than `shared_types` is generated by `build.rs`: Abigen output plus
+//! the embedded `BYTECODE`/`DEPLOYED_BYTECODE`/`abi()` helpers, written to `$OUT_DIR` and
+//! `include!`d verbatim so nothing here needs to be regenerated or committed by hand. Keep this
+//! list in sync with `build.rs`'s `CONTRACTS`.
+
+pub mod shared_types;
+
+pub mod polygon_zk_evm {
+    include!(concat!(env!("OUT_DIR"), "/polygon_zk_evm.rs"));
+}
+
+pub mod polygon_zk_evm_bridge {
+    include!(concat!(env!("OUT_DIR"), "/polygon_zk_evm_bridge.rs"));
+}
+
+pub mod polygon_zk_evm_global_exit_root_l2 {
+    include!(concat!(env!("OUT_DIR"), "/polygon_zk_evm_global_exit_root_l2.rs"));
+}
+
+pub mod polygon_zk_evm_global_exit_root {
+    include!(concat!(env!("OUT_DIR"), "/polygon_zk_evm_global_exit_root.rs"));
+}
+
+pub mod polygon_zk_evm_timelock {
+    include!(concat!(env!("OUT_DIR"), "/polygon_zk_evm_timelock.rs"));
+}
+
+pub mod verifier {
+    include!(concat!(env!("OUT_DIR"), "/verifier.rs"));
+}
+
+pub mod verifier_rollup_helper_mock {
+    include!(concat!(env!("OUT_DIR"), "/verifier_rollup_helper_mock.rs"));
+}
+
+pub mod erc20_permit_mock {
+    include!(concat!(env!("OUT_DIR"), "/erc20_permit_mock.rs"));
+}
@@ -0,0 +1,31 @@
+//! Solidity structs shared across more than one contract's ABI.
+//!
+//! `build.rs` generates one Abigen module per contract independently, which would otherwise give
+//! each contract sharing one of these structs its own duplicate, incompatible Rust type for it.
+//! Defining them once here and referencing them from call sites avoids that.
+
+use ethers::{
+    contract::{EthAbiCodec, EthAbiType},
+    types::{Address, Bytes},
+};
+
+/// `PolygonZkEVM.BatchData`, the per-batch argument to `sequenceBatches`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, EthAbiType, EthAbiCodec)]
+pub struct BatchData {
+    pub transactions: Bytes,
+    pub global_exit_root: [u8; 32],
+    pub timestamp: u64,
+    pub min_forced_timestamp: u64,
+}
+
+/// `PolygonZkEVM.InitializePackedParameters`, part of `initialize`'s argument list.
+#[derive(Clone, Debug, Default, Eq, PartialEq, EthAbiType, EthAbiCodec)]
+pub struct InitializePackedParameters {
+    pub admin: Address,
+    pub trusted_sequencer: Address,
+    pub pending_state_timeout: u64,
+    pub trusted_aggregator: Address,
+    pub trusted_aggregator_timeout: u64,
+    pub chain_id: u64,
+    pub force_batch_allowed: bool,
+}
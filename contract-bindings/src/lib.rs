@@ -0,0 +1,3 @@
+//! Generated and hand-written bindings for the zkEVM L1 contracts.
+
+pub mod bindings;
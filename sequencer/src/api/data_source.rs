@@ -0,0 +1,42 @@
+//! The sequencer-specific data a query API backing store must provide, on top of the generic
+//! availability/status data every [`ExtensibleDataSource`](hotshot_query_service::data_source::ExtensibleDataSource)
+//! already gets from `hotshot_query_service`.
+//!
+//! [`fs::DataSource`](super::fs::DataSource) and [`sql::DataSource`](super::sql::DataSource) are
+//! the two implementations, backed by the file system and Postgres respectively.
+
+use super::l1_sync::L1Status;
+use crate::network;
+use async_trait::async_trait;
+use clap::Parser;
+use hotshot_types::traits::metrics::Metrics;
+
+/// Sequencer-specific data and bootstrapping a query API backing store must provide.
+#[async_trait]
+pub trait SequencerDataSource<N: network::Type>: Sized {
+    /// Command-line options for constructing this data source.
+    type Options: Parser + Clone + Send;
+
+    /// Open (or create) the data source.
+    async fn create(opt: Self::Options) -> anyhow::Result<Self>;
+
+    /// A metrics handle that HotShot will populate, and which this data source serves back out
+    /// through the `status` API.
+    fn populate_metrics(&self) -> Box<dyn Metrics>;
+
+    /// The last L1 sequence/verify status this node has observed, persisted across restarts.
+    fn l1_status(&self) -> L1Status;
+
+    /// Persist that batch `num_batch` has been sequenced on L1, observed at L1 block `l1_block`
+    /// (if the log carried one).
+    fn set_last_sequenced(&mut self, num_batch: u64, l1_block: Option<u64>);
+
+    /// Persist that batch `num_batch` has been verified on L1, observed at L1 block `l1_block`
+    /// (if the log carried one).
+    fn set_last_verified(&mut self, num_batch: u64, l1_block: Option<u64>);
+
+    /// Persist the L1 block height up to which `SequenceBatches`/`VerifyBatches` events have
+    /// been scanned, so [`l1_sync_loop`](super::l1_sync::l1_sync_loop) can resume from here
+    /// after a restart instead of rescanning from genesis.
+    fn set_last_scanned_block(&mut self, l1_block: u64);
+}
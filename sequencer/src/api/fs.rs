@@ -0,0 +1,94 @@
+//! Query API data source backed by the file system.
+
+use super::{data_source::SequencerDataSource, l1_sync::L1Status, Fs};
+use crate::network;
+use async_trait::async_trait;
+use hotshot_query_service::data_source::fs::FileSystemDataSource;
+use hotshot_types::traits::metrics::{Metrics, PrometheusMetrics};
+use std::{
+    ops::{Deref, DerefMut},
+    path::PathBuf,
+};
+
+/// Name of the sidecar file `L1Status` is persisted to, alongside the rest of the query data in
+/// `storage_path`. A sidecar file (rather than extending `FileSystemDataSource`'s own on-disk
+/// format) keeps this addition independent of that format's versioning.
+const L1_STATUS_FILE: &str = "l1_status.json";
+
+pub struct DataSource<N: network::Type> {
+    fs: FileSystemDataSource<N>,
+    l1_status_path: PathBuf,
+    l1_status: L1Status,
+}
+
+impl<N: network::Type> DataSource<N> {
+    fn persist_l1_status(&self) {
+        if let Ok(bytes) = serde_json::to_vec(&self.l1_status) {
+            if let Err(err) = std::fs::write(&self.l1_status_path, bytes) {
+                tracing::warn!(%err, "failed to persist L1 status");
+            }
+        }
+    }
+}
+
+impl<N: network::Type> Deref for DataSource<N> {
+    type Target = FileSystemDataSource<N>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.fs
+    }
+}
+
+impl<N: network::Type> DerefMut for DataSource<N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.fs
+    }
+}
+
+#[async_trait]
+impl<N: network::Type> SequencerDataSource<N> for DataSource<N> {
+    type Options = Fs;
+
+    async fn create(opt: Fs) -> anyhow::Result<Self> {
+        let fs = if opt.reset_store {
+            FileSystemDataSource::create(&opt.storage_path).await?
+        } else {
+            FileSystemDataSource::open(&opt.storage_path).await?
+        };
+
+        let l1_status_path = opt.storage_path.join(L1_STATUS_FILE);
+        let l1_status = std::fs::read(&l1_status_path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+
+        Ok(Self {
+            fs,
+            l1_status_path,
+            l1_status,
+        })
+    }
+
+    fn populate_metrics(&self) -> Box<dyn Metrics> {
+        Box::new(PrometheusMetrics::default())
+    }
+
+    fn l1_status(&self) -> L1Status {
+        self.l1_status
+    }
+
+    fn set_last_sequenced(&mut self, num_batch: u64, _l1_block: Option<u64>) {
+        self.l1_status.last_sequenced = Some(num_batch);
+        self.persist_l1_status();
+    }
+
+    fn set_last_verified(&mut self, num_batch: u64, _l1_block: Option<u64>) {
+        self.l1_status.last_verified = Some(num_batch);
+        self.persist_l1_status();
+    }
+
+    fn set_last_scanned_block(&mut self, l1_block: u64) {
+        self.l1_status.last_scanned_block = Some(l1_block);
+        self.persist_l1_status();
+    }
+}
@@ -0,0 +1,186 @@
+//! Tracks L1 finalization status of HotShot blocks.
+//!
+//! This module polls the `PolygonZkEVM` rollup contract on L1 for `SequenceBatches` and
+//! `VerifyBatches` events and records the highest sequenced and verified batch numbers in the
+//! query data source, where the `l1` API module serves them. The batch numbers are not checked
+//! against the blocks the query service has stored.
+
+use super::data_source::SequencerDataSource;
+use crate::network;
+use async_std::sync::{Arc, RwLock};
+use contract_bindings::bindings::polygon_zk_evm::{PolygonZkEVM, SequenceBatchesFilter, VerifyBatchesFilter};
+use ethers::{
+    providers::{Middleware, Provider},
+    types::{Address, Filter, U64},
+};
+use futures::FutureExt;
+use hotshot_query_service::{data_source::ExtensibleDataSource, Error};
+use hotshot_types::traits::metrics::{Gauge, Metrics};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tide_disco::{api::ApiError, Api};
+
+/// The shared app state type used by the `l1` API module.
+type State<N, D> = Arc<RwLock<ExtensibleDataSource<D, N>>>;
+
+/// The last L1 batch numbers this node has observed as sequenced and verified.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct L1Status {
+    pub last_sequenced: Option<u64>,
+    pub last_verified: Option<u64>,
+    /// The L1 block height up to which `SequenceBatches`/`VerifyBatches` events have been
+    /// scanned. This is an L1 block number, not a batch number, and is what polling resumes
+    /// from after a restart; `last_sequenced`/`last_verified` are batch numbers and are not on
+    /// the same scale.
+    pub last_scanned_block: Option<u64>,
+}
+
+/// Metrics populated as new batches are observed on L1.
+pub struct L1Metrics {
+    last_sequenced: Box<dyn Gauge>,
+    last_verified: Box<dyn Gauge>,
+}
+
+impl L1Metrics {
+    pub fn new(metrics: &dyn Metrics) -> Self {
+        let subgroup = metrics.subgroup("l1".into());
+        Self {
+            last_sequenced: subgroup.create_gauge("last_sequenced_batch".into(), None),
+            last_verified: subgroup.create_gauge("last_verified_batch".into(), None),
+        }
+    }
+}
+
+/// Build the `l1` API module, exposing [`L1Status`] as `GET /l1/status` alongside the
+/// `last_sequenced_batch`/`last_verified_batch` gauges `l1_sync_loop` already populates, so
+/// clients that want the raw batch numbers (rather than scraping metrics) have a direct endpoint.
+pub fn define_api<N, D>() -> Result<Api<State<N, D>, Error>, ApiError>
+where
+    N: network::Type,
+    D: SequencerDataSource<N> + Send + Sync + 'static,
+{
+    let mut api = Api::<State<N, D>, Error>::new(toml::from_str(include_str!("../../api/l1.toml"))?)?;
+
+    api.get("status", |_req, state| {
+        async move { Ok(state.l1_status()) }.boxed()
+    })?;
+
+    Ok(api)
+}
+
+/// Poll the rollup contract on L1 for `SequenceBatches`/`VerifyBatches` events, updating the data
+/// source's L1 status and `metrics` as new events arrive. The blocks stored in the data source
+/// are not consulted.
+///
+/// Runs forever, resuming from the last scanned L1 block height persisted in the data source
+/// across restarts. If the
+/// underlying `eth_newFilter` expires (e.g. because the L1 node restarted), the filter is
+/// recreated and the gap since the last reconciled block is backfilled with `eth_getLogs`.
+pub async fn l1_sync_loop<N, D>(
+    state: Arc<RwLock<ExtensibleDataSource<D, N>>>,
+    l1_provider: Provider<ethers::providers::Http>,
+    rollup_address: Address,
+    metrics: L1Metrics,
+) where
+    N: network::Type,
+    D: SequencerDataSource<N> + Send + Sync + 'static,
+{
+    let rollup = PolygonZkEVM::new(rollup_address, l1_provider.clone().into());
+    let mut from_block = {
+        let ds = state.read().await;
+        ds.l1_status()
+            .last_scanned_block
+            .map(U64::from)
+            .unwrap_or_default()
+    };
+
+    loop {
+        let filter = Filter::new()
+            .address(rollup_address)
+            .from_block(from_block)
+            .events(["SequenceBatches(uint64)", "VerifyBatches(uint64,bytes32,address)"]);
+
+        let filter_id = match l1_provider.new_filter(ethers::types::FilterKind::Logs(&filter)).await {
+            Ok(id) => id,
+            Err(err) => {
+                tracing::warn!(%err, "failed to install L1 event filter, retrying");
+                async_std::task::sleep(Duration::from_secs(5)).await;
+                continue;
+            }
+        };
+
+        loop {
+            async_std::task::sleep(l1_provider.get_interval()).await;
+
+            let changes = match l1_provider.get_filter_changes::<_, ethers::types::Log>(filter_id).await {
+                Ok(changes) => changes,
+                Err(_) => {
+                    // The filter likely expired on the node; recreate it and backfill the
+                    // events we may have missed via `get_logs`.
+                    tracing::warn!("L1 event filter expired, recreating and backfilling");
+                    let backfill = filter.clone().to_block(
+                        l1_provider
+                            .get_block_number()
+                            .await
+                            .unwrap_or(from_block),
+                    );
+                    match l1_provider.get_logs(&backfill).await {
+                        Ok(logs) => {
+                            from_block = reconcile(&state, &rollup, from_block, logs, &metrics).await;
+                        }
+                        Err(err) => tracing::warn!(%err, "failed to backfill L1 events"),
+                    }
+                    break;
+                }
+            };
+
+            from_block = reconcile(&state, &rollup, from_block, changes, &metrics).await;
+        }
+    }
+}
+
+/// Decode each log into a `(batch number, L1 block)` pair, update the data source's stored
+/// cursor, and bump the metrics. Returns the L1 block to resume polling from: `from_block`
+/// unchanged if `logs` is empty (an empty poll tells us nothing about how far the node has
+/// scanned), otherwise one past the highest L1 block among `logs`.
+async fn reconcile<N, D>(
+    state: &Arc<RwLock<ExtensibleDataSource<D, N>>>,
+    rollup: &PolygonZkEVM<Provider<ethers::providers::Http>>,
+    from_block: U64,
+    logs: Vec<ethers::types::Log>,
+    metrics: &L1Metrics,
+) -> U64
+where
+    N: network::Type,
+    D: SequencerDataSource<N> + Send + Sync + 'static,
+{
+    if logs.is_empty() {
+        return from_block;
+    }
+
+    let mut max_block = U64::zero();
+    let mut ds = state.write().await;
+
+    for log in logs {
+        max_block = max_block.max(log.block_number.unwrap_or_default());
+
+        if let Ok(event) = rollup.decode_event::<SequenceBatchesFilter>(
+            "SequenceBatches",
+            log.topics.clone(),
+            log.data.clone(),
+        ) {
+            ds.set_last_sequenced(event.num_batch, log.block_number.map(|n| n.as_u64()));
+            metrics.last_sequenced.set(event.num_batch as usize);
+        }
+        if let Ok(event) =
+            rollup.decode_event::<VerifyBatchesFilter>("VerifyBatches", log.topics, log.data)
+        {
+            ds.set_last_verified(event.num_batch, log.block_number.map(|n| n.as_u64()));
+            metrics.last_verified.set(event.num_batch as usize);
+        }
+    }
+
+    let scanned_through = max_block + 1;
+    ds.set_last_scanned_block(scanned_through.as_u64());
+    from_block.max(scanned_through)
+}
@@ -1,27 +1,57 @@
 //! Sequencer-specific API options and initialization.
 
 use super::{
-    data_source::SequencerDataSource, endpoints, fs, sql, update::update_loop, AppState, Consensus,
-    NodeIndex, SequencerNode,
+    data_source::SequencerDataSource,
+    endpoints,
+    fs,
+    l1_sync::{self, l1_sync_loop, L1Metrics},
+    peer_sync::peer_sync_loop,
+    sql,
+    stream::{self, DecidedBlock},
+    update::update_loop,
+    AppState, Consensus, NodeIndex, SequencerNode,
 };
 use crate::network;
+use async_broadcast::broadcast;
 use async_std::{
     sync::{Arc, RwLock},
     task::spawn,
 };
 use clap::Parser;
-use futures::future::{BoxFuture, TryFutureExt};
+use contract_bindings::bindings::{
+    erc20_permit_mock::ERC20PermitMock, polygon_zk_evm::PolygonZkEVM,
+    polygon_zk_evm_global_exit_root::PolygonZkEVMGlobalExitRoot,
+};
+use ethers::{
+    middleware::SignerMiddleware,
+    providers::{Middleware, Provider},
+    signers::{LocalWallet, Signer},
+    types::Address,
+};
+use crate::l1_batch::BatchDriver;
+use surf_disco::Url;
+use futures::{
+    future::{BoxFuture, TryFutureExt},
+    StreamExt,
+};
 use hotshot_query_service::{data_source::ExtensibleDataSource, status, Error};
 use hotshot_types::traits::metrics::{Metrics, NoMetrics};
 use std::path::PathBuf;
 use tide_disco::App;
 
+/// Capacity of the broadcast channel that fans decided blocks out to live subscribers. Oldest
+/// unread events are dropped once a lagging subscriber falls this far behind.
+const STREAM_BUFFER_SIZE: usize = 1024;
+
 #[derive(Clone, Debug)]
 pub struct Options {
     pub http: Http,
     pub query_sql: Option<Sql>,
     pub query_fs: Option<Fs>,
     pub submit: Option<Submit>,
+    pub l1: Option<L1>,
+    pub peer: Option<PeerSync>,
+    pub batch: Option<Batch>,
 }
 
 impl From<Http> for Options {
@@ -31,6 +61,9 @@ impl From<Http> for Options {
             query_sql: None,
             query_fs: None,
             submit: None,
+            l1: None,
+            peer: None,
+            batch: None,
         }
     }
 }
@@ -54,6 +87,25 @@ impl Options {
         self
     }
 
+    /// Track L1 finalization status by polling the rollup contract.
+    pub fn l1(mut self, opt: L1) -> Self {
+        self.l1 = Some(opt);
+        self
+    }
+
+    /// Run as a read-only replica that mirrors its query data from `opt.url` instead of
+    /// participating in HotShot consensus. Requires `query_sql` or `query_fs` to also be set.
+    pub fn peer(mut self, opt: PeerSync) -> Self {
+        self.peer = Some(opt);
+        self
+    }
+
+    /// Continuously sequence decided blocks as L1 batches, alongside the sequencer.
+    pub fn batch(mut self, opt: Batch) -> Self {
+        self.batch = Some(opt);
+        self
+    }
+
     /// Whether these options will run the query API.
     pub fn has_query_module(&self) -> bool {
         self.query_sql.is_some() || self.query_fs.is_some()
@@ -69,6 +121,23 @@ impl Options {
         N: network::Type,
         F: FnOnce(Box<dyn Metrics>) -> BoxFuture<'static, (Consensus<N>, NodeIndex)>,
     {
+        // A replica never starts consensus: all of its query data is mirrored from `peer`
+        // instead of being derived from locally observed consensus events.
+        if let Some(peer) = self.peer {
+            let node = if let Some(opt) = self.query_sql {
+                init_as_replica::<N, sql::DataSource<N>>(opt, peer, init_handle, self.http.port)
+                    .await?
+            } else if let Some(opt) = self.query_fs {
+                init_as_replica::<N, fs::DataSource<N>>(opt, peer, init_handle, self.http.port)
+                    .await?
+            } else {
+                anyhow::bail!(
+                    "Options::peer requires a query API module (query_sql or query_fs)"
+                );
+            };
+            return Ok(node);
+        }
+
         // The server state type depends on whether we are running a query API or not, so we handle
         // the two cases differently.
         let node = if let Some(opt) = self.query_sql {
@@ -76,6 +145,8 @@ impl Options {
                 opt,
                 init_handle,
                 self.submit.is_some(),
+                self.l1,
+                self.batch,
                 self.http.port,
             )
             .await?
@@ -84,6 +155,8 @@ impl Options {
                 opt,
                 init_handle,
                 self.submit.is_some(),
+                self.l1,
+                self.batch,
                 self.http.port,
             )
             .await?
@@ -128,6 +201,51 @@ pub struct Http {
 #[derive(Parser, Clone, Copy, Debug, Default)]
 pub struct Submit;
 
+/// Options for tracking L1 finalization status.
+#[derive(Parser, Clone, Debug)]
+pub struct L1 {
+    /// URL of an L1 Ethereum JSON-RPC provider.
+    #[clap(long, env = "ESPRESSO_SEQUENCER_L1_PROVIDER")]
+    pub provider: String,
+
+    /// Address of the `PolygonZkEVM` rollup contract on L1.
+    #[clap(long, env = "ESPRESSO_SEQUENCER_L1_ROLLUP_ADDRESS")]
+    pub rollup_address: Address,
+}
+
+/// Options for running as a read-only replica of an upstream sequencer node.
+#[derive(Parser, Clone, Debug)]
+pub struct PeerSync {
+    /// Base URL of an upstream node's HTTP API to mirror; blocks are read from its
+    /// `availability/stream/blocks` route under this URL.
+    #[clap(long, env = "ESPRESSO_SEQUENCER_PEER_API_URL")]
+    pub url: Url,
+}
+
+/// Options for continuously sequencing decided blocks as L1 batches.
+#[derive(Parser, Clone, Debug)]
+pub struct Batch {
+    /// URL of an L1 Ethereum JSON-RPC provider.
+    #[clap(long, env = "ESPRESSO_SEQUENCER_L1_PROVIDER")]
+    pub provider: String,
+
+    /// Address of the `PolygonZkEVM` rollup contract on L1.
+    #[clap(long, env = "ESPRESSO_SEQUENCER_L1_ROLLUP_ADDRESS")]
+    pub rollup_address: Address,
+
+    /// Address of the Matic ERC20 token contract on L1.
+    #[clap(long, env = "ESPRESSO_SEQUENCER_L1_MATIC_ADDRESS")]
+    pub matic_address: Address,
+
+    /// Address of the `PolygonZkEVMGlobalExitRoot` contract on L1.
+    #[clap(long, env = "ESPRESSO_SEQUENCER_L1_GLOBAL_EXIT_ROOT_ADDRESS")]
+    pub global_exit_root_address: Address,
+
+    /// Private key of the trusted sequencer account used to submit batches.
+    #[clap(long, env = "ESPRESSO_SEQUENCER_BATCH_SIGNER_KEY")]
+    pub signer_key: LocalWallet,
+}
+
 /// Options for the query API module backed by a Postgres database.
 #[derive(Parser, Clone, Debug)]
 pub struct Sql {
@@ -168,6 +286,8 @@ async fn init_with_query_module<N, D>(
     opt: D::Options,
     init_handle: impl FnOnce(Box<dyn Metrics>) -> BoxFuture<'static, (Consensus<N>, NodeIndex)>,
     submit: bool,
+    l1: Option<L1>,
+    batch: Option<Batch>,
     port: u16,
 ) -> anyhow::Result<SequencerNode<N>>
 where
@@ -178,6 +298,7 @@ where
 
     let ds = D::create(opt).await?;
     let metrics = ds.populate_metrics();
+    let l1_metrics = l1.is_some().then(|| L1Metrics::new(metrics.as_ref()));
 
     // Start up handle
     let (mut handle, node_index) = init_handle(metrics).await;
@@ -189,6 +310,45 @@ where
     // the first events emitted by consensus.
     let events = handle.get_event_stream(Default::default()).await.0;
 
+    // Get a second, independent event stream to fan decided blocks out to live subscribers of
+    // the streaming endpoints, so that `update_loop` and each subscriber get their own copy.
+    let decided_events = handle.get_event_stream(Default::default()).await.0;
+    let (mut stream_tx, stream_rx) = broadcast(STREAM_BUFFER_SIZE);
+    // Drop the oldest unread event once a lagging subscriber falls `STREAM_BUFFER_SIZE` behind,
+    // rather than the default of dropping the newest (which would stall every subscriber, not
+    // just the lagging one, until it catches up).
+    stream_tx.set_overflow(true);
+    // Keep one receiver alive for the lifetime of the server, so the channel never closes for
+    // lack of subscribers between client connections.
+    let _stream_keep_alive = stream_rx.clone();
+    spawn(async move {
+        let mut decided_events = decided_events;
+        while let Some(event) = decided_events.next().await {
+            for block in DecidedBlock::from_event(event) {
+                // An error here just means no one is currently subscribed; that's fine.
+                let _ = stream_tx.try_broadcast(block);
+            }
+        }
+    });
+
+    // Continuously sequence decided blocks as L1 batches, if configured. Like `decided_events`
+    // above, this is its own independent event stream so the batch driver's consumption can
+    // never starve `update_loop` or the stream subscribers of events.
+    if let Some(batch) = batch {
+        let batch_events = handle.get_event_stream(Default::default()).await.0;
+        let l1_provider = Provider::try_from(batch.provider)?;
+        let chain_id = l1_provider.get_chainid().await?.as_u64();
+        let client = Arc::new(SignerMiddleware::new(
+            l1_provider,
+            batch.signer_key.with_chain_id(chain_id),
+        ));
+        let rollup = PolygonZkEVM::new(batch.rollup_address, client.clone());
+        let matic = ERC20PermitMock::new(batch.matic_address, client.clone());
+        let global_exit_root =
+            PolygonZkEVMGlobalExitRoot::new(batch.global_exit_root_address, client);
+        spawn(BatchDriver::new(rollup, matic, global_exit_root).run(batch_events));
+    }
+
     let state: State<N, D> = Arc::new(RwLock::new(ExtensibleDataSource::new(ds, handle.clone())));
     let mut app = App::<_, Error>::with_state(state.clone());
 
@@ -201,12 +361,31 @@ where
     // Initialize availability and status APIs
     let availability_api = endpoints::availability::<N, D>()?;
     let status_api = status::define_api::<State<N, D>>(&Default::default())?;
+    let stream_api = stream::define_api::<N, D>(stream_rx)?;
 
     // Register modules in app
     app.register_module("availability", availability_api)?
-        .register_module("status", status_api)?;
+        .register_module("status", status_api)?
+        .register_module("stream", stream_api)?;
+
+    // Start tracking L1 finalization status, if configured. The `l1` API module exposes the
+    // same `L1Status` the gauges above are derived from, for clients that want the raw batch
+    // numbers directly rather than scraping metrics.
+    if let (Some(l1), Some(l1_metrics)) = (l1, l1_metrics) {
+        let l1_api = l1_sync::define_api::<N, D>()?;
+        app.register_module("l1", l1_api)?;
+
+        let l1_provider = Provider::try_from(l1.provider)?;
+        spawn(l1_sync_loop(
+            state.clone(),
+            l1_provider,
+            l1.rollup_address,
+            l1_metrics,
+        ));
+    }
 
     let update_task = spawn(async move {
+        let _stream_keep_alive = _stream_keep_alive;
         futures::join!(
             app.serve(format!("0.0.0.0:{port}"))
                 .map_err(anyhow::Error::from),
@@ -221,3 +400,59 @@ where
         update_task,
     })
 }
+
+/// Start the server as a read-only replica, mirroring query data from `peer` instead of
+/// deriving it from locally observed consensus events. The HTTP surface (availability, status)
+/// is identical to [`init_with_query_module`], so clients cannot tell a replica from a primary.
+///
+/// This intentionally does not route mirrored blocks through [`update_loop`] itself:
+/// `update_loop` consumes genuine consensus [`Event`](hotshot::types::Event)s (decided leaves
+/// with real QCs), which a replica never has — it only ever sees the already-decided
+/// [`BlockQueryData`] its peer serves. Instead, [`peer_sync_loop`] calls
+/// [`update::record_block`](super::update::record_block), the same helper `update_loop` calls
+/// for each decided leaf, so the one piece of bookkeeping that matters for API parity (storing
+/// each block, in height order, exactly once) is shared code rather than two copies that could
+/// drift apart.
+async fn init_as_replica<N, D>(
+    opt: D::Options,
+    peer: PeerSync,
+    init_handle: impl FnOnce(Box<dyn Metrics>) -> BoxFuture<'static, (Consensus<N>, NodeIndex)>,
+    port: u16,
+) -> anyhow::Result<SequencerNode<N>>
+where
+    N: network::Type,
+    D: SequencerDataSource<N> + Send + Sync + 'static,
+{
+    type State<N, D> = Arc<RwLock<AppState<N, D>>>;
+
+    let ds = D::create(opt).await?;
+    let metrics = ds.populate_metrics();
+
+    // A handle is still needed to report a node index and populate consensus metrics, but
+    // consensus is never started on it; the handle's event stream is unused because all query
+    // data comes from `peer` instead.
+    let (handle, node_index) = init_handle(metrics).await;
+
+    let state: State<N, D> = Arc::new(RwLock::new(ExtensibleDataSource::new(ds, handle.clone())));
+    let mut app = App::<_, Error>::with_state(state.clone());
+
+    let availability_api = endpoints::availability::<N, D>()?;
+    let status_api = status::define_api::<State<N, D>>(&Default::default())?;
+    app.register_module("availability", availability_api)?
+        .register_module("status", status_api)?;
+
+    let update_task = spawn(async move {
+        futures::join!(
+            app.serve(format!("0.0.0.0:{port}"))
+                .map_err(anyhow::Error::from),
+            peer_sync_loop::<N, D, _>(state, peer.url),
+        )
+        .0
+    });
+
+    Ok(SequencerNode {
+        handle,
+        node_index,
+        update_task,
+    })
+}
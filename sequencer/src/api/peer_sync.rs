@@ -0,0 +1,112 @@
+//! Read-only replica sync.
+//!
+//! Drives a node's query data from an upstream node's availability API instead of from locally
+//! observed consensus events, for nodes that want to scale read traffic horizontally off the
+//! validators without participating in consensus themselves.
+
+use super::{data_source::SequencerDataSource, update::record_block};
+use crate::network;
+use async_std::sync::{Arc, RwLock};
+use futures::StreamExt;
+use hotshot_query_service::{availability::BlockQueryData, data_source::ExtensibleDataSource};
+use std::time::Duration;
+use surf_disco::Url;
+
+/// Subscribe to `peer`'s `/availability/stream/blocks` socket endpoint and write each newly-received block
+/// into `state`, verifying continuity by height so that a restart resumes exactly where sync
+/// left off and a gap or reordering triggers a reconnect rather than silently skipping blocks.
+pub async fn peer_sync_loop<N, D, H>(state: Arc<RwLock<ExtensibleDataSource<D, H>>>, peer: Url)
+where
+    N: network::Type,
+    D: SequencerDataSource<N> + Send + Sync + 'static,
+    H: Send + Sync + 'static,
+{
+    let client = surf_disco::Client::<hotshot_query_service::Error>::new(peer);
+
+    loop {
+        let from = state.read().await.block_height().await.unwrap_or(0);
+
+        let mut blocks = match client
+            .socket(&format!("availability/stream/blocks?from={from}"))
+            .subscribe::<BlockQueryData<N>>()
+            .await
+        {
+            Ok(blocks) => blocks,
+            Err(err) => {
+                tracing::warn!(%err, "failed to connect to peer, retrying");
+                async_std::task::sleep(Duration::from_secs(5)).await;
+                continue;
+            }
+        };
+
+        let mut next_height = from;
+        while let Some(block) = blocks.next().await {
+            let block = match block {
+                Ok(block) => block,
+                Err(err) => {
+                    tracing::warn!(%err, "error reading from peer, reconnecting");
+                    break;
+                }
+            };
+            if !check_continuity(&mut next_height, block.height()) {
+                tracing::warn!(
+                    expected = next_height,
+                    got = block.height(),
+                    "gap in peer block stream, reconnecting"
+                );
+                break;
+            }
+
+            if let Err(err) = record_block(&state, block).await {
+                tracing::warn!(%err, height = next_height - 1, "failed to store block mirrored from peer");
+                break;
+            }
+        }
+    }
+}
+
+/// Returns whether `height` is the expected next height given `next_height`, advancing
+/// `next_height` on success. Factored out of the loop above so it can be exercised without
+/// standing up a real peer connection: in particular, that the live tail the `stream` module
+/// hands off after replay (which starts exactly at the replay boundary, per the dedup fix in
+/// `stream::subscribe`) is seen as contiguous here rather than tripping a reconnect.
+fn check_continuity(next_height: &mut u64, height: u64) -> bool {
+    if height != *next_height {
+        return false;
+    }
+    *next_height += 1;
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::check_continuity;
+
+    #[test]
+    fn replay_then_live_is_contiguous_past_the_replay_boundary() {
+        // Replay covers heights [0, 5); the live tail, deduped at the seam, starts at height 5
+        // rather than repeating it. A fresh replica should see this as one contiguous sequence
+        // and keep syncing, instead of tripping the continuity check and reconnecting forever.
+        let mut next_height = 0;
+        for height in 0..5 {
+            assert!(
+                check_continuity(&mut next_height, height),
+                "replay should be contiguous"
+            );
+        }
+        for height in 5..8 {
+            assert!(
+                check_continuity(&mut next_height, height),
+                "live tail should continue past the replay boundary without repeating it"
+            );
+        }
+        assert_eq!(next_height, 8);
+    }
+
+    #[test]
+    fn gap_breaks_continuity() {
+        let mut next_height = 0;
+        assert!(check_continuity(&mut next_height, 0));
+        assert!(!check_continuity(&mut next_height, 2));
+    }
+}
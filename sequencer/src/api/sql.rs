@@ -0,0 +1,132 @@
+//! Query API data source backed by a Postgres database.
+
+use super::{data_source::SequencerDataSource, l1_sync::L1Status, Sql};
+use crate::network;
+use async_trait::async_trait;
+use hotshot_query_service::data_source::sql::SqlDataSource;
+use hotshot_types::traits::metrics::{Metrics, PrometheusMetrics};
+use sqlx::Row;
+use std::ops::{Deref, DerefMut};
+
+/// `id` of the single row the `l1_status` table ever holds; there is one L1 status per node, not
+/// per batch, so this is just an upsert target rather than a real key.
+const L1_STATUS_ROW: i32 = 0;
+
+/// `SequencerDataSource` is synchronous (so it can be called from sync code that already holds
+/// `state`'s lock), but persisting to Postgres is inherently async. Rather than blocking the
+/// executor on every read, `l1_status` is cached in memory after `create` loads it once, and kept
+/// in sync with the on-disk row on every write; a write that fails to persist is logged and
+/// retried on the next successful one, rather than losing the update in memory too.
+pub struct DataSource<N: network::Type> {
+    sql: SqlDataSource<N>,
+    l1_status: L1Status,
+}
+
+impl<N: network::Type> DataSource<N> {
+    async fn load_l1_status(sql: &SqlDataSource<N>) -> anyhow::Result<L1Status> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS l1_status (
+                id BIGINT PRIMARY KEY,
+                last_sequenced BIGINT,
+                last_verified BIGINT,
+                last_scanned_block BIGINT
+            )",
+        )
+        .execute(sql.pool())
+        .await?;
+
+        let row = sqlx::query(
+            "SELECT last_sequenced, last_verified, last_scanned_block FROM l1_status
+             WHERE id = $1",
+        )
+        .bind(L1_STATUS_ROW)
+        .fetch_optional(sql.pool())
+        .await?;
+
+        Ok(row
+            .map(|row| L1Status {
+                last_sequenced: row.get::<Option<i64>, _>("last_sequenced").map(|n| n as u64),
+                last_verified: row.get::<Option<i64>, _>("last_verified").map(|n| n as u64),
+                last_scanned_block: row
+                    .get::<Option<i64>, _>("last_scanned_block")
+                    .map(|n| n as u64),
+            })
+            .unwrap_or_default())
+    }
+
+    /// Upsert the whole cached status in one row, rather than one query per field, so each write
+    /// stores every field together. Each setter writes separately, so a restart between two
+    /// related updates (e.g. `last_sequenced` and `last_scanned_block` from the same log batch)
+    /// can find the first stored without the second.
+    fn persist_l1_status(&self) {
+        let sql = self.sql.pool().clone();
+        let status = self.l1_status;
+        async_std::task::spawn(async move {
+            let result = sqlx::query(
+                "INSERT INTO l1_status (id, last_sequenced, last_verified, last_scanned_block)
+                 VALUES ($1, $2, $3, $4)
+                 ON CONFLICT (id) DO UPDATE SET
+                     last_sequenced = excluded.last_sequenced,
+                     last_verified = excluded.last_verified,
+                     last_scanned_block = excluded.last_scanned_block",
+            )
+            .bind(L1_STATUS_ROW)
+            .bind(status.last_sequenced.map(|n| n as i64))
+            .bind(status.last_verified.map(|n| n as i64))
+            .bind(status.last_scanned_block.map(|n| n as i64))
+            .execute(&sql)
+            .await;
+            if let Err(err) = result {
+                tracing::warn!(%err, "failed to persist L1 status");
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl<N: network::Type> SequencerDataSource<N> for DataSource<N> {
+    type Options = Sql;
+
+    async fn create(opt: Sql) -> anyhow::Result<Self> {
+        let sql = SqlDataSource::create(opt).await?;
+        let l1_status = Self::load_l1_status(&sql).await?;
+        Ok(Self { sql, l1_status })
+    }
+
+    fn populate_metrics(&self) -> Box<dyn Metrics> {
+        Box::new(PrometheusMetrics::default())
+    }
+
+    fn l1_status(&self) -> L1Status {
+        self.l1_status
+    }
+
+    fn set_last_sequenced(&mut self, num_batch: u64, _l1_block: Option<u64>) {
+        self.l1_status.last_sequenced = Some(num_batch);
+        self.persist_l1_status();
+    }
+
+    fn set_last_verified(&mut self, num_batch: u64, _l1_block: Option<u64>) {
+        self.l1_status.last_verified = Some(num_batch);
+        self.persist_l1_status();
+    }
+
+    fn set_last_scanned_block(&mut self, l1_block: u64) {
+        self.l1_status.last_scanned_block = Some(l1_block);
+        self.persist_l1_status();
+    }
+}
+
+impl<N: network::Type> Deref for DataSource<N> {
+    type Target = SqlDataSource<N>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.sql
+    }
+}
+
+impl<N: network::Type> DerefMut for DataSource<N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.sql
+    }
+}
@@ -0,0 +1,120 @@
+//! Streaming subscription endpoints for live decided blocks.
+//!
+//! Clients previously had no way to learn about newly-decided blocks other than repeatedly
+//! polling the availability API. This module fans the consensus event stream that `update_loop`
+//! already consumes out through a broadcast channel, so each subscriber (and `update_loop`
+//! itself) gets its own copy, and exposes that fan-out as `/availability/stream/blocks` and
+//! `/availability/stream/leaves` socket routes on the same `App`.
+
+use super::{data_source::SequencerDataSource, AppState};
+use crate::network;
+use async_broadcast::Receiver;
+use async_std::sync::{Arc, RwLock};
+use futures::{future::BoxFuture, stream::StreamExt, FutureExt};
+use hotshot::types::{Event, EventType};
+use hotshot_query_service::{
+    availability::{BlockQueryData, LeafQueryData},
+    Error,
+};
+use hotshot_types::{data::Leaf, traits::node_implementation::NodeType};
+use tide_disco::{api::ApiError, Api, RequestParams};
+
+/// The shared app state type used by every module registered on the sequencer's `App`.
+type State<N, D> = Arc<RwLock<AppState<N, D>>>;
+
+/// A decided block, paired with the leaf that committed it. Broadcast to every subscriber
+/// whenever consensus decides a new block.
+#[derive(Clone, Debug)]
+pub struct DecidedBlock<Types: NodeType> {
+    pub leaf: Leaf<Types>,
+    pub block: BlockQueryData<Types>,
+}
+
+impl<Types: NodeType> DecidedBlock<Types> {
+    /// Extract the newly-decided blocks from a consensus event, if it is a `Decide` event.
+    pub fn from_event(event: Event<Types>) -> Vec<Self> {
+        let EventType::Decide { leaf_chain, .. } = event.event else {
+            return vec![];
+        };
+        leaf_chain
+            .iter()
+            .filter_map(|leaf| {
+                let block = BlockQueryData::try_from(leaf.clone()).ok()?;
+                Some(Self {
+                    leaf: leaf.clone(),
+                    block,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Build the `stream` API module.
+///
+/// `events` is a receiver on the broadcast channel that `init_with_query_module` fans the
+/// consensus decided-block stream out through; each call to a route handler gets its own clone
+/// via [`Receiver::clone`], so multiple subscribers can tail the same underlying stream
+/// independently.
+pub fn define_api<N, D>(
+    events: Receiver<DecidedBlock<N>>,
+) -> Result<Api<State<N, D>, Error>, ApiError>
+where
+    N: network::Type,
+    D: SequencerDataSource<N> + Send + Sync + 'static,
+{
+    let mut api = Api::<State<N, D>, Error>::new(toml::from_str(include_str!(
+        "../../api/stream.toml"
+    ))?)?;
+
+    api.stream("subscribe_blocks", move |req, state| {
+        subscribe(req, state, events.clone(), |decided| decided.block).boxed()
+    })?
+    .stream("subscribe_leaves", move |req, state| {
+        subscribe(req, state, events.clone(), |decided| {
+            LeafQueryData::from(decided.leaf)
+        })
+        .boxed()
+    })?;
+
+    Ok(api)
+}
+
+/// Replay stored items from `?from=<height>` (if given) and then tail `events` live, mapping
+/// each decided block through `project` to the type the client expects.
+///
+/// The live receiver's cursor was fixed when `define_api` cloned it, which predates (or at best
+/// coincides with) the replay snapshot taken here, so the live tail can repeat heights already
+/// covered by replay. Heights are contiguous and monotonically increasing, so we dedupe at the
+/// seam by dropping any live item at or below the last replayed height, rather than re-deriving
+/// the cut-over from `events` (which has no notion of height bounds of its own).
+fn subscribe<'a, N, D, T>(
+    req: RequestParams,
+    state: &'a State<N, D>,
+    events: Receiver<DecidedBlock<N>>,
+    project: impl Fn(DecidedBlock<N>) -> T + Send + Sync + 'static,
+) -> BoxFuture<'a, Result<impl futures::Stream<Item = Result<T, Error>> + 'static, Error>>
+where
+    N: network::Type,
+    D: SequencerDataSource<N> + Send + Sync + 'static,
+    T: Send + 'static,
+{
+    async move {
+        let from = req.opt_integer_param("from")?.unwrap_or(0) as u64;
+
+        let replay_to = {
+            let ds = state.read().await;
+            ds.block_height().await?
+        };
+        let replay = {
+            let ds = state.read().await;
+            ds.get_block_range(from..replay_to).await?
+        };
+
+        let live = events.filter_map(move |decided| {
+            let decided = (decided.block.height() >= replay_to).then_some(decided);
+            async move { decided.map(|decided| Ok(project(decided))) }
+        });
+        Ok(futures::stream::iter(replay.into_iter().map(Ok)).chain(live))
+    }
+    .boxed()
+}
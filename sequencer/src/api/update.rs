@@ -0,0 +1,53 @@
+//! Drives the query data source from the consensus event stream.
+
+use super::data_source::SequencerDataSource;
+use crate::network;
+use async_std::sync::{Arc, RwLock};
+use futures::StreamExt;
+use hotshot::types::{Event, EventType};
+use hotshot_query_service::{availability::BlockQueryData, data_source::ExtensibleDataSource};
+
+/// Consume consensus decided-block events and store each decided block, in height order, exactly
+/// once. This is a primary node's only source of query data; unlike
+/// [`peer_sync_loop`](super::peer_sync::peer_sync_loop), it derives blocks from genuine decided
+/// leaves rather than mirroring them from a peer, so [`EventType::Decide`] is the only event kind
+/// handled here.
+pub async fn update_loop<N, D, H>(
+    state: Arc<RwLock<ExtensibleDataSource<D, H>>>,
+    mut events: impl futures::Stream<Item = Event<N>> + Unpin,
+) where
+    N: network::Type,
+    D: SequencerDataSource<N> + Send + Sync + 'static,
+    H: Send + Sync + 'static,
+{
+    while let Some(event) = events.next().await {
+        let EventType::Decide { leaf_chain, .. } = event.event else {
+            continue;
+        };
+        for leaf in leaf_chain.iter().rev() {
+            let Ok(block) = BlockQueryData::try_from(leaf.clone()) else {
+                continue;
+            };
+            if let Err(err) = record_block(&state, block).await {
+                tracing::warn!(%err, "failed to store decided block");
+            }
+        }
+    }
+}
+
+/// Store `block`, in height order, exactly once — the one piece of bookkeeping that matters for
+/// HTTP surface parity between a primary ([`update_loop`], fed genuine consensus events) and a
+/// replica ([`peer_sync_loop`](super::peer_sync::peer_sync_loop), fed a peer's already-decided
+/// blocks). Factored out so both call exactly this code instead of keeping two copies that could
+/// drift out of sync with each other.
+pub(crate) async fn record_block<N, D, H>(
+    state: &Arc<RwLock<ExtensibleDataSource<D, H>>>,
+    block: BlockQueryData<N>,
+) -> anyhow::Result<()>
+where
+    N: network::Type,
+    D: SequencerDataSource<N> + Send + Sync + 'static,
+    H: Send + Sync + 'static,
+{
+    state.write().await.insert_block(block).await
+}
@@ -0,0 +1,287 @@
+//! Drives continuous batch submission to the L1 rollup contract.
+//!
+//! Unlike the one-shot `deploy` example, which sequences a single hard-coded batch, this module
+//! runs for the lifetime of the sequencer: every HotShot block that consensus decides is packed
+//! into a `BatchData` and submitted to `PolygonZkEVM::sequence_batches`, with retries on
+//! transient L1 errors and, across restarts, a check against the rollup's own
+//! `lastBatchSequenced` counter so a batch that is already confirmed on L1 is never resubmitted.
+//! This does not cover the narrow window between broadcasting a submission and it confirming (a
+//! crash exactly there can still double-submit that one batch); `sequence` awaits the receipt
+//! inline specifically to keep that window to a single `.await` rather than leaving it open until
+//! some later, incidental re-decide of the same height.
+
+use contract_bindings::bindings::{
+    erc20_permit_mock::ERC20PermitMock, polygon_zk_evm::PolygonZkEVM,
+    polygon_zk_evm_global_exit_root::PolygonZkEVMGlobalExitRoot, shared_types::BatchData,
+};
+use ethers::{
+    providers::Middleware,
+    types::{BlockNumber, H256, U256},
+};
+use futures::StreamExt;
+use hotshot::types::{Event, EventType};
+use hotshot_types::traits::node_implementation::NodeType;
+use lru::LruCache;
+use std::{num::NonZeroUsize, sync::Arc, time::Duration};
+
+/// Status of a batch this driver has submitted this process's lifetime, keyed by batch number.
+/// Consulted only to avoid re-submitting a batch redelivered by the event stream (e.g. a
+/// re-decide) within the same run; it does not survive a restart, so `sequence` also checks the
+/// rollup's own `lastBatchSequenced` counter before submitting, which is what actually makes
+/// restart-after-confirmation safe.
+enum PendingBatch {
+    Submitted(H256),
+    Confirmed,
+}
+
+/// Continuously sequences decided HotShot blocks as L1 batches.
+pub struct BatchDriver<M: Middleware> {
+    rollup: PolygonZkEVM<M>,
+    matic: ERC20PermitMock<M>,
+    global_exit_root: PolygonZkEVMGlobalExitRoot<M>,
+    /// Batches already submitted or confirmed in this run; see [`PendingBatch`] for what makes
+    /// re-submission after a restart idempotent.
+    pending: LruCache<u64, PendingBatch>,
+    /// Matic allowance the rollup contract is known to have, refreshed only when insufficient.
+    allowance: U256,
+}
+
+const MAX_RETRIES: u32 = 5;
+const PENDING_CACHE_SIZE: usize = 256;
+
+impl<M: Middleware + 'static> BatchDriver<M> {
+    pub fn new(
+        rollup: PolygonZkEVM<M>,
+        matic: ERC20PermitMock<M>,
+        global_exit_root: PolygonZkEVMGlobalExitRoot<M>,
+    ) -> Self {
+        Self {
+            rollup,
+            matic,
+            global_exit_root,
+            pending: LruCache::new(NonZeroUsize::new(PENDING_CACHE_SIZE).unwrap()),
+            allowance: U256::zero(),
+        }
+    }
+
+    /// Consume the consensus decided-block event stream, sequencing each decided block as it
+    /// arrives. This is the same event stream `update_loop` consumes to populate the query API,
+    /// so a block is only ever sequenced once it has actually been decided by consensus.
+    pub async fn run<Types: NodeType>(
+        mut self,
+        mut events: impl futures::Stream<Item = Event<Types>> + Unpin,
+    ) {
+        while let Some(event) = events.next().await {
+            let EventType::Decide { leaf_chain, .. } = event.event else {
+                continue;
+            };
+            // `leaf_chain` is newest first; sequence in height order so each batch number lines up
+            // with the rollup's `lastBatchSequenced` counter.
+            for leaf in leaf_chain.iter().rev() {
+                let batch_number = leaf.get_height();
+                if matches!(self.pending.get(&batch_number), Some(PendingBatch::Confirmed)) {
+                    continue;
+                }
+
+                let transactions = leaf.get_block_payload().map(|payload| payload.transactions());
+                let Some(transactions) = transactions else {
+                    continue;
+                };
+
+                if let Err(err) = self.sequence(batch_number, transactions).await {
+                    tracing::error!(batch_number, %err, "failed to sequence batch after retries");
+                }
+            }
+        }
+    }
+
+    /// Pack `transactions` into a `BatchData` and submit it, retrying transient RPC errors with
+    /// exponential backoff, then await the submission's receipt so a revert is caught as part of
+    /// this call rather than only on some later, incidental re-decide of the same batch number.
+    ///
+    /// Idempotent across a restart: a batch the rollup contract already considers sequenced (per
+    /// its own `lastBatchSequenced` counter) is never resubmitted, regardless of whether this
+    /// process's in-memory `pending` map remembers submitting it.
+    async fn sequence(
+        &mut self,
+        batch_number: u64,
+        transactions: impl Into<Vec<u8>>,
+    ) -> anyhow::Result<()> {
+        if matches!(self.pending.get(&batch_number), Some(PendingBatch::Confirmed)) {
+            return Ok(());
+        }
+
+        let last_sequenced = self.rollup.last_batch_sequenced().call().await?;
+        if batch_number <= last_sequenced {
+            self.pending.put(batch_number, PendingBatch::Confirmed);
+            return Ok(());
+        }
+
+        let client = self.rollup.client();
+        let global_exit_root = self.global_exit_root.get_last_global_exit_root().call().await?;
+        let timestamp = client
+            .get_block(BlockNumber::Latest)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("L1 has no latest block"))?
+            .timestamp
+            .as_u64();
+
+        let batch = BatchData {
+            transactions: transactions.into().into(),
+            global_exit_root,
+            timestamp,
+            min_forced_timestamp: 0,
+        };
+
+        let fee = self.rollup.get_current_batch_fee().call().await?;
+        self.ensure_allowance(fee).await?;
+
+        let pending_tx = retry(MAX_RETRIES, || async {
+            Ok(self.rollup.sequence_batches(vec![batch.clone()]).send().await?)
+        })
+        .await?;
+        let tx_hash = pending_tx.tx_hash();
+        self.pending.put(batch_number, PendingBatch::Submitted(tx_hash));
+
+        let receipt = pending_tx
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("L1 dropped transaction {tx_hash:?}"))?;
+        self.pending.put(batch_number, PendingBatch::Confirmed);
+        anyhow::ensure!(
+            receipt.status == Some(1u64.into()),
+            "batch {batch_number} reverted on L1 (tx {tx_hash:?})"
+        );
+        Ok(())
+    }
+
+    /// Approve the rollup contract to spend `amount` of Matic, but only if the cached allowance
+    /// is insufficient — avoids an `approve` transaction on every batch.
+    async fn ensure_allowance(&mut self, amount: U256) -> anyhow::Result<()> {
+        if self.allowance >= amount {
+            return Ok(());
+        }
+        retry(MAX_RETRIES, || async {
+            self.matic
+                .approve(self.rollup.address(), amount)
+                .send()
+                .await?
+                .await?;
+            Ok(())
+        })
+        .await?;
+        self.allowance = amount;
+        Ok(())
+    }
+}
+
+/// Retry `f` up to `max_retries` times with exponential backoff, for transient L1 RPC errors.
+async fn retry<T, F, Fut>(max_retries: u32, mut f: F) -> anyhow::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        match f().await {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt < max_retries => {
+                attempt += 1;
+                let backoff = Duration::from_millis(200 * 2u64.pow(attempt));
+                tracing::warn!(attempt, %err, "transient error submitting batch, retrying");
+                async_std::task::sleep(backoff).await;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ethers::types::{Address, Block, TransactionReceipt};
+
+    /// Exercises the part of `BatchDriver` that actually talks to L1: packing a batch and
+    /// submitting `sequence_batches`. Building a full `Event<Types>`/decided leaf chain requires
+    /// a concrete `NodeType` impl that lives outside this crate, so this drives `sequence`
+    /// directly rather than going through `run` — `run` itself is a thin loop over `sequence`
+    /// once a leaf's transactions are extracted.
+    #[async_std::test]
+    async fn sequence_submits_sequence_batches_for_a_fresh_batch() {
+        let (provider, mock) = ethers::providers::Provider::mocked();
+        let client = Arc::new(provider);
+
+        let rollup = PolygonZkEVM::new(Address::zero(), client.clone());
+        let matic = ERC20PermitMock::new(Address::zero(), client.clone());
+        let global_exit_root = PolygonZkEVMGlobalExitRoot::new(Address::zero(), client);
+        let mut driver = BatchDriver::new(rollup, matic, global_exit_root);
+
+        // `last_batch_sequenced` — nothing sequenced yet on L1, so batch 1 is new.
+        mock.push(0u64).unwrap();
+        // `get_last_global_exit_root`
+        mock.push([0u8; 32]).unwrap();
+        // `get_block(Latest)`
+        mock.push(Block::<H256> {
+            timestamp: 1234.into(),
+            ..Default::default()
+        })
+        .unwrap();
+        // `get_current_batch_fee`
+        mock.push(U256::from(1)).unwrap();
+        // `matic.approve(..).send().await` (insufficient cached allowance on a fresh driver)
+        mock.push(U256::zero()).unwrap(); // nonce
+        mock.push(U256::from(21_000)).unwrap(); // gas estimate
+        mock.push(U256::from(1)).unwrap(); // gas price
+        mock.push(H256::zero()).unwrap(); // tx hash
+        mock.push(Some(TransactionReceipt {
+            status: Some(1u64.into()),
+            ..Default::default()
+        }))
+        .unwrap();
+        // `rollup.sequence_batches(..).send()`
+        mock.push(U256::from(1)).unwrap(); // nonce
+        mock.push(U256::from(21_000)).unwrap(); // gas estimate
+        mock.push(U256::from(1)).unwrap(); // gas price
+        mock.push(H256::repeat_byte(1)).unwrap(); // tx hash
+        // `.await` on the returned `PendingTransaction`, confirming it landed successfully.
+        mock.push(Some(TransactionReceipt {
+            status: Some(1u64.into()),
+            ..Default::default()
+        }))
+        .unwrap();
+
+        driver.sequence(1, vec![0x12, 0x34]).await.unwrap();
+
+        assert!(matches!(
+            driver.pending.get(&1),
+            Some(PendingBatch::Confirmed)
+        ));
+    }
+
+    /// A batch the rollup contract already reports as sequenced (e.g. this node crashed after
+    /// broadcasting but before recording it, then restarted with an empty `pending` map) must not
+    /// be resubmitted.
+    #[async_std::test]
+    async fn sequence_skips_a_batch_already_sequenced_on_chain() {
+        let (provider, mock) = ethers::providers::Provider::mocked();
+        let client = Arc::new(provider);
+
+        let rollup = PolygonZkEVM::new(Address::zero(), client.clone());
+        let matic = ERC20PermitMock::new(Address::zero(), client.clone());
+        let global_exit_root = PolygonZkEVMGlobalExitRoot::new(Address::zero(), client);
+        let mut driver = BatchDriver::new(rollup, matic, global_exit_root);
+
+        // `last_batch_sequenced` — batch 1 was already sequenced before this process started.
+        // `eth_call` returns the ABI-encoded `uint64` return value as hex bytes.
+        mock.push(ethers::types::Bytes::from(ethers::abi::encode(&[
+            ethers::abi::Token::Uint(1u64.into()),
+        ])))
+        .unwrap();
+
+        driver.sequence(1, vec![0x12, 0x34]).await.unwrap();
+
+        assert!(matches!(
+            driver.pending.get(&1),
+            Some(PendingBatch::Confirmed)
+        ));
+    }
+}